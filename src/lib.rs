@@ -23,11 +23,25 @@
 //!     ErrCode(0)
 //! }
 //! ```
+//!
+//! ## Features
+//!
+//! - `std` (off by default, keeps the crate `no_std`): adds [`ExternError`], a
+//!   `#[repr(C)]` FFI out-parameter carrying an [`ErrCode`] plus a heap message;
+//!   [`call_with_errcode`], which runs a fallible closure and writes the result
+//!   (catching panics) into an `ExternError`; [`register_name`], a process-wide
+//!   registry of code names consulted by [`ErrCode`]'s [`Display`](core::fmt::Display)
+//!   impl; and an `impl` of [`std::process::Termination`] for `ErrCode`, so
+//!   `fn main() -> ErrCode` works.
 #![feature(try_trait_v2)]
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::convert::Infallible;
-use core::fmt::{Debug, Formatter};
+use core::fmt::{Debug, Display, Formatter};
+use core::marker::PhantomData;
 use core::num::NonZeroU32;
 use core::ops::{ControlFlow, FromResidual, Try};
 
@@ -58,6 +72,64 @@ impl ErrCode {
             panic!("[{:?}] {}", self.0, msg);
         }
     }
+
+    /// Converts to a [`Result`], carrying the error code as a [`NonZeroU32`].
+    pub fn into_result(self) -> Result<(), NonZeroU32> {
+        match NonZeroU32::new(self.0) {
+            Some(code) => Err(code),
+            None => Ok(()),
+        }
+    }
+
+    /// Discards the error code, keeping only whether it succeeded.
+    pub fn ok(self) -> Option<()> {
+        self.into_result().ok()
+    }
+
+    /// Discards the success case, keeping the error code if there was one.
+    pub fn err(self) -> Option<NonZeroU32> {
+        self.into_result().err()
+    }
+
+    /// Applies `f` to the error code if this is an error, leaving success untouched.
+    pub fn map_err<F: FnOnce(NonZeroU32) -> NonZeroU32>(self, f: F) -> ErrCode {
+        match NonZeroU32::new(self.0) {
+            Some(code) => ErrCode(f(code).into()),
+            None => self,
+        }
+    }
+
+    /// Runs `f` only if `self.is_ok()`, otherwise propagates `self` unchanged.
+    pub fn and_then<F: FnOnce() -> ErrCode>(self, f: F) -> ErrCode {
+        if self.is_ok() {
+            f()
+        } else {
+            self
+        }
+    }
+
+    /// The top bit of the code, as in Windows `HRESULT` (1 typically means failure).
+    pub fn severity(self) -> u8 {
+        ((self.0 >> 31) & 0x1) as u8
+    }
+
+    /// Bits 16..=26, the subsystem/facility field of an `HRESULT`-style code.
+    pub fn facility(self) -> u16 {
+        ((self.0 >> 16) & 0x7FF) as u16
+    }
+
+    /// The low 16 bits, the subsystem-specific value of an `HRESULT`-style code.
+    pub fn value(self) -> u16 {
+        (self.0 & 0xFFFF) as u16
+    }
+
+    /// Reassembles a code from its `severity`/`facility`/`value` fields, the
+    /// inverse of [`ErrCode::severity`], [`ErrCode::facility`] and [`ErrCode::value`].
+    pub fn from_parts(severity: u8, facility: u16, value: u16) -> Self {
+        let severity = (severity as u32 & 0x1) << 31;
+        let facility = (facility as u32 & 0x7FF) << 16;
+        ErrCode(severity | facility | value as u32)
+    }
 }
 
 impl Debug for ErrCode {
@@ -66,6 +138,64 @@ impl Debug for ErrCode {
     }
 }
 
+/// Renders as `E{n} ({name})` when `n` has a known name, or `E{n}` otherwise.
+///
+/// Under the `std` feature, names registered process-wide via [`register_name`]
+/// are consulted automatically. In `no_std`, use [`ErrCode::with_names`] with a
+/// static lookup table instead.
+impl Display for ErrCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "std")]
+        if let Some(name) = lookup_registered_name(self.0) {
+            return write!(f, "E{} ({})", self.0, name);
+        }
+        write!(f, "E{}", self.0)
+    }
+}
+
+/// An [`ErrCode`] paired with a static `(code, name)` lookup table, for `no_std`
+/// callers that want [`Display`] to show a name without a process-wide registry.
+pub struct Named<'a> {
+    code: ErrCode,
+    names: &'a [(u32, &'static str)],
+}
+
+impl Display for Named<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.names.iter().find(|(code, _)| *code == self.code.0) {
+            Some((_, name)) => write!(f, "E{} ({})", self.code.0, name),
+            None => write!(f, "E{}", self.code.0),
+        }
+    }
+}
+
+impl ErrCode {
+    /// Pairs this code with a static `(code, name)` table for display purposes,
+    /// e.g. `ErrCode(3).with_names(&[(3, "ENOENT")])` displays as `E3 (ENOENT)`.
+    pub fn with_names<'a>(self, names: &'a [(u32, &'static str)]) -> Named<'a> {
+        Named { code: self, names }
+    }
+}
+
+/// Associates `code` with `name` process-wide, so [`Display`] for [`ErrCode`]
+/// renders it as `E{code} ({name})` from then on.
+#[cfg(feature = "std")]
+pub fn register_name(code: u32, name: &'static str) {
+    name_registry().lock().unwrap().insert(code, name);
+}
+
+#[cfg(feature = "std")]
+fn name_registry() -> &'static std::sync::Mutex<std::collections::BTreeMap<u32, &'static str>> {
+    static REGISTRY: std::sync::OnceLock<std::sync::Mutex<std::collections::BTreeMap<u32, &'static str>>> =
+        std::sync::OnceLock::new();
+    REGISTRY.get_or_init(|| std::sync::Mutex::new(std::collections::BTreeMap::new()))
+}
+
+#[cfg(feature = "std")]
+fn lookup_registered_name(code: u32) -> Option<&'static str> {
+    name_registry().lock().unwrap().get(&code).copied()
+}
+
 pub struct ErrCodeResidual(NonZeroU32);
 
 impl Try for ErrCode {
@@ -105,6 +235,219 @@ impl FromResidual<Result<Infallible, ErrCode>> for ErrCode {
     }
 }
 
+/// Like [`ErrCode`], but can recover a typed error enum `E` from the raw code.
+///
+/// `E` must round-trip through [`NonZeroU32`] via [`TryFrom`]/[`Into`]. The
+/// wire representation is still a plain `u32` (this type is `#[repr(transparent)]`
+/// over it), so FFI functions keep returning a 32-bit value; Rust callers can
+/// additionally `match errcode.reason()` against `E`'s variants.
+#[repr(transparent)]
+#[must_use]
+pub struct TypedErrCode<E>(pub u32, PhantomData<E>);
+
+impl<E> Copy for TypedErrCode<E> {}
+
+impl<E> Clone for TypedErrCode<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> PartialEq for TypedErrCode<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<E> Eq for TypedErrCode<E> {}
+
+impl<E> Debug for TypedErrCode<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "E{}", self.0)
+    }
+}
+
+impl<E> TypedErrCode<E> {
+    pub const fn new(code: u32) -> Self {
+        TypedErrCode(code, PhantomData)
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_err(&self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl<E: TryFrom<NonZeroU32>> TypedErrCode<E> {
+    /// Decodes the raw code back into `E`, or `None` on success or if the code
+    /// doesn't correspond to a known variant.
+    pub fn reason(self) -> Option<E> {
+        NonZeroU32::new(self.0).and_then(|code| E::try_from(code).ok())
+    }
+}
+
+impl<E: Into<NonZeroU32>> From<E> for TypedErrCode<E> {
+    fn from(reason: E) -> Self {
+        TypedErrCode::new(reason.into().into())
+    }
+}
+
+pub struct TypedErrCodeResidual<E>(NonZeroU32, PhantomData<E>);
+
+impl<E> Try for TypedErrCode<E> {
+    type Output = ();
+    type Residual = TypedErrCodeResidual<E>;
+
+    fn from_output(_: Self::Output) -> Self {
+        TypedErrCode::new(0)
+    }
+
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        match NonZeroU32::new(self.0) {
+            Some(r) => ControlFlow::Break(TypedErrCodeResidual(r, PhantomData)),
+            None => ControlFlow::Continue(()),
+        }
+    }
+}
+
+impl<E> FromResidual for TypedErrCode<E> {
+    fn from_residual(residual: <Self as Try>::Residual) -> Self {
+        TypedErrCode::new(residual.0.into())
+    }
+}
+
+impl<E> FromResidual<ErrCodeResidual> for TypedErrCode<E> {
+    fn from_residual(residual: ErrCodeResidual) -> Self {
+        TypedErrCode::new(residual.0.into())
+    }
+}
+
+impl<E> FromResidual<Result<Infallible, TypedErrCode<E>>> for TypedErrCode<E> {
+    fn from_residual(residual: Result<Infallible, TypedErrCode<E>>) -> Self {
+        match residual {
+            Err(err) => err,
+            Ok(_) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ErrCode {
+    /// The nonzero exit byte for this code, truncated from the full 32 bits
+    /// and clamped so a truncation to zero can never be reported as success.
+    fn exit_code_byte(self) -> u8 {
+        (self.0 as u8).max(1)
+    }
+}
+
+/// Lets `ErrCode` be returned directly from `main`, so `?` works there too.
+///
+/// A zero code reports success; any other code is printed to stderr (via the
+/// [`Debug`] impl) and mapped to a process exit code, truncating to [`u8`] as
+/// exit codes do. A nonzero `ErrCode` is never allowed to collapse into exit
+/// code 0, since that would be reported as success.
+#[cfg(feature = "std")]
+impl std::process::Termination for ErrCode {
+    fn report(self) -> std::process::ExitCode {
+        use std::process::ExitCode;
+
+        if self.0 == 0 {
+            ExitCode::SUCCESS
+        } else {
+            std::eprintln!("Error: {:?}", self);
+            ExitCode::from(self.exit_code_byte())
+        }
+    }
+}
+
+/// An FFI out-parameter carrying an [`ErrCode`] plus a human-readable message.
+///
+/// Pass `&mut ExternError` as the last argument of an FFI function. On success,
+/// write [`ExternError::success`]; on failure, write [`ExternError::new_error`]
+/// with a nonzero code and a message, which the caller must release with
+/// [`ExternError::free_message`]. The layout is `#[repr(C)]` so it is ABI-stable.
+#[cfg(feature = "std")]
+#[repr(C)]
+pub struct ExternError {
+    pub code: ErrCode,
+    pub message: *mut std::os::raw::c_char,
+}
+
+#[cfg(feature = "std")]
+impl ExternError {
+    /// A success value with no message.
+    pub fn success() -> Self {
+        ExternError {
+            code: ErrCode(0),
+            message: core::ptr::null_mut(),
+        }
+    }
+
+    /// An error value carrying `code` and a heap-allocated copy of `message`.
+    ///
+    /// If `message` contains an interior NUL byte, it is truncated at the
+    /// first one rather than discarded, so a C string can still be formed.
+    pub fn new_error(code: ErrCode, message: &str) -> Self {
+        let message = std::ffi::CString::new(message).unwrap_or_else(|e| {
+            let valid_up_to = e.nul_position();
+            std::ffi::CString::new(&message.as_bytes()[..valid_up_to]).unwrap()
+        });
+        ExternError {
+            code,
+            message: message.into_raw(),
+        }
+    }
+
+    /// Frees a message previously written into an `ExternError`.
+    ///
+    /// # Safety
+    /// `message` must either be null or have been produced by
+    /// [`ExternError::new_error`] and not freed already.
+    pub unsafe fn free_message(message: *mut std::os::raw::c_char) {
+        if !message.is_null() {
+            drop(std::ffi::CString::from_raw(message));
+        }
+    }
+}
+
+/// The code written to `out` by [`call_with_errcode`] when `f` panics.
+#[cfg(feature = "std")]
+pub const PANIC_ERR_CODE: ErrCode = ErrCode(u32::MAX);
+
+/// Runs `f` inside [`std::panic::catch_unwind`] and writes the outcome to `out`.
+///
+/// `Ok(())` becomes [`ExternError::success`]; `Err(e)` becomes `e.into()` with
+/// `e`'s [`Display`](std::fmt::Display) as the message — this requires `E: Display`
+/// in addition to the `Into<ErrCode>` needed for the code itself, so the message
+/// can always be synthesized automatically. A panic is caught before it can
+/// unwind across the FFI boundary (undefined behavior) and is reported as
+/// [`PANIC_ERR_CODE`] with the panic payload as its message.
+#[cfg(feature = "std")]
+pub fn call_with_errcode<F, E>(out: &mut ExternError, f: F)
+where
+    F: FnOnce() -> Result<(), E> + std::panic::UnwindSafe,
+    E: Into<ErrCode> + std::fmt::Display,
+{
+    *out = match std::panic::catch_unwind(f) {
+        Ok(Ok(())) => ExternError::success(),
+        Ok(Err(e)) => {
+            let message = std::string::ToString::to_string(&e);
+            ExternError::new_error(e.into(), &message)
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(std::string::ToString::to_string)
+                .or_else(|| payload.downcast_ref::<std::string::String>().cloned())
+                .unwrap_or_else(|| std::string::String::from("unknown panic payload"));
+            ExternError::new_error(PANIC_ERR_CODE, &message)
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +461,217 @@ mod tests {
         }
         assert_eq!(inner(), ErrCode(1));
     }
+
+    #[test]
+    fn result_bridging_methods() {
+        for (code, expect_ok) in [(0u32, true), (1, false), (42, false)] {
+            let c = ErrCode(code);
+            assert_eq!(c.is_ok(), expect_ok);
+            assert_eq!(c.ok().is_some(), expect_ok);
+            assert_eq!(c.err().is_some(), !expect_ok);
+            assert_eq!(c.into_result().is_ok(), expect_ok);
+        }
+
+        assert_eq!(
+            ErrCode(5).map_err(|c| NonZeroU32::new(c.get() + 1).unwrap()),
+            ErrCode(6)
+        );
+        assert_eq!(
+            ErrCode(0).map_err(|c| NonZeroU32::new(c.get() + 1).unwrap()),
+            ErrCode(0)
+        );
+
+        assert_eq!(ErrCode(0).and_then(|| ErrCode(7)), ErrCode(7));
+        assert_eq!(ErrCode(3).and_then(|| ErrCode(7)), ErrCode(3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn termination_exit_byte_never_collapses_to_success() {
+        assert_eq!(ErrCode(1).exit_code_byte(), 1);
+        assert_eq!(ErrCode(255).exit_code_byte(), 255);
+        // 256 truncates to a low byte of 0, which must clamp back up to 1.
+        assert_eq!(ErrCode(256).exit_code_byte(), 1);
+        assert_eq!(ErrCode(257).exit_code_byte(), 1);
+    }
+
+    #[test]
+    fn typed_err_code_round_trips() {
+        #[derive(Debug, Eq, PartialEq)]
+        enum MyError {
+            Timeout,
+        }
+
+        impl TryFrom<NonZeroU32> for MyError {
+            type Error = ();
+
+            fn try_from(code: NonZeroU32) -> Result<Self, Self::Error> {
+                match code.get() {
+                    1 => Ok(MyError::Timeout),
+                    _ => Err(()),
+                }
+            }
+        }
+
+        impl From<MyError> for NonZeroU32 {
+            fn from(err: MyError) -> Self {
+                match err {
+                    MyError::Timeout => NonZeroU32::new(1).unwrap(),
+                }
+            }
+        }
+
+        fn inner() -> TypedErrCode<MyError> {
+            TypedErrCode::from(MyError::Timeout)
+        }
+
+        assert_eq!(inner().reason(), Some(MyError::Timeout));
+        assert_eq!(TypedErrCode::<MyError>::new(0).reason(), None);
+    }
+
+    #[test]
+    fn typed_err_code_accepts_plain_result_via_try() {
+        #[derive(Debug, Eq, PartialEq)]
+        enum MyError {
+            Timeout,
+        }
+
+        impl TryFrom<NonZeroU32> for MyError {
+            type Error = ();
+
+            fn try_from(code: NonZeroU32) -> Result<Self, Self::Error> {
+                match code.get() {
+                    1 => Ok(MyError::Timeout),
+                    _ => Err(()),
+                }
+            }
+        }
+
+        impl From<MyError> for NonZeroU32 {
+            fn from(err: MyError) -> Self {
+                match err {
+                    MyError::Timeout => NonZeroU32::new(1).unwrap(),
+                }
+            }
+        }
+
+        fn fallible() -> Result<(), TypedErrCode<MyError>> {
+            Err(TypedErrCode::from(MyError::Timeout))
+        }
+
+        fn inner() -> TypedErrCode<MyError> {
+            fallible()?;
+            TypedErrCode::new(0)
+        }
+
+        assert_eq!(inner().reason(), Some(MyError::Timeout));
+    }
+
+    #[test]
+    fn hresult_style_parts_round_trip() {
+        let code = ErrCode::from_parts(1, 0x4, 0x2A);
+        assert_eq!(code.severity(), 1);
+        assert_eq!(code.facility(), 0x4);
+        assert_eq!(code.value(), 0x2A);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn named_display() {
+        const NAMES: &[(u32, &str)] = &[(3, "ENOENT")];
+        assert_eq!(ErrCode(3).with_names(NAMES).to_string(), "E3 (ENOENT)");
+        assert_eq!(ErrCode(4).with_names(NAMES).to_string(), "E4");
+
+        register_name(5, "EIO");
+        assert_eq!(ErrCode(5).to_string(), "E5 (EIO)");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn extern_error_success_and_new_error() {
+        let ok = ExternError::success();
+        assert!(ok.code.is_ok());
+        assert!(ok.message.is_null());
+
+        let err = ExternError::new_error(ErrCode(7), "boom");
+        assert_eq!(err.code, ErrCode(7));
+        let message = unsafe { std::ffi::CStr::from_ptr(err.message) };
+        assert_eq!(message.to_str().unwrap(), "boom");
+        unsafe { ExternError::free_message(err.message) };
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn extern_error_new_error_truncates_message_at_first_nul() {
+        let err = ExternError::new_error(ErrCode(8), "bad\0message");
+        let message = unsafe { std::ffi::CStr::from_ptr(err.message) };
+        assert_eq!(message.to_str().unwrap(), "bad");
+        unsafe { ExternError::free_message(err.message) };
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Debug)]
+    struct FfiError(u32, &'static str);
+
+    #[cfg(feature = "std")]
+    impl std::fmt::Display for FfiError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.1)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<FfiError> for ErrCode {
+        fn from(e: FfiError) -> Self {
+            ErrCode(e.0)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn call_with_errcode_ok() {
+        let mut out = ExternError::success();
+        call_with_errcode(&mut out, || -> Result<(), FfiError> { Ok(()) });
+        assert!(out.code.is_ok());
+        assert!(out.message.is_null());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn call_with_errcode_err() {
+        let mut out = ExternError::success();
+        call_with_errcode(&mut out, || -> Result<(), FfiError> {
+            Err(FfiError(99, "my ffi error"))
+        });
+        assert_eq!(out.code, ErrCode(99));
+        let message = unsafe { std::ffi::CStr::from_ptr(out.message) };
+        assert_eq!(message.to_str().unwrap(), "my ffi error");
+        unsafe { ExternError::free_message(out.message) };
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn call_with_errcode_catches_literal_panic() {
+        let mut out = ExternError::success();
+        call_with_errcode(&mut out, || -> Result<(), FfiError> {
+            panic!("boom");
+        });
+        assert_eq!(out.code, PANIC_ERR_CODE);
+        let message = unsafe { std::ffi::CStr::from_ptr(out.message) };
+        assert_eq!(message.to_str().unwrap(), "boom");
+        unsafe { ExternError::free_message(out.message) };
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn call_with_errcode_catches_formatted_panic() {
+        let mut out = ExternError::success();
+        call_with_errcode(&mut out, || -> Result<(), FfiError> {
+            panic!("{} failed", "operation");
+        });
+        assert_eq!(out.code, PANIC_ERR_CODE);
+        let message = unsafe { std::ffi::CStr::from_ptr(out.message) };
+        assert_eq!(message.to_str().unwrap(), "operation failed");
+        unsafe { ExternError::free_message(out.message) };
+    }
 }